@@ -0,0 +1,148 @@
+//! Connection helper built on the runtime-agnostic `async-tls` crate instead of tokio's
+//! own TLS wrappers, so the handshake can run on executors other than tokio.
+//!
+//! Everything here is expressed in terms of `futures_io::{AsyncRead, AsyncWrite}` rather
+//! than tokio's I/O traits. `MaybeTlsStream` bridges the two by implementing tokio's traits
+//! on top of a `futures_io` stream, which lets the rest of this crate's framing (built on
+//! tokio's traits) be reused unchanged.
+use std::io::Result as IoResult;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_io::{AsyncRead as FuturesAsyncRead, AsyncWrite as FuturesAsyncWrite};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use async_tls::{client::TlsStream, TlsConnector};
+
+use tungstenite::client::uri_mode;
+use tungstenite::handshake::client::Response;
+use tungstenite::protocol::WebSocketConfig;
+use tungstenite::stream::Mode;
+use tungstenite::Error;
+
+use crate::connect::domain;
+use crate::{client_async_with_config, IntoClientRequest, WebSocketStream};
+
+/// A stream that might be protected with TLS, built on the runtime-agnostic `async-tls`
+/// backend.
+pub enum MaybeTlsStream<S> {
+    /// Unencrypted socket stream.
+    Plain(S),
+    /// Encrypted socket stream.
+    Tls(TlsStream<S>),
+}
+
+fn poll_read_futures<T: FuturesAsyncRead + Unpin>(
+    io: &mut T,
+    cx: &mut Context<'_>,
+    buf: &mut ReadBuf<'_>,
+) -> Poll<IoResult<()>> {
+    match Pin::new(io).poll_read(cx, buf.initialize_unfilled()) {
+        Poll::Ready(Ok(n)) => {
+            buf.advance(n);
+            Poll::Ready(Ok(()))
+        }
+        Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+        Poll::Pending => Poll::Pending,
+    }
+}
+
+impl<S: FuturesAsyncRead + FuturesAsyncWrite + Unpin> AsyncRead for MaybeTlsStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<IoResult<()>> {
+        match self.get_mut() {
+            MaybeTlsStream::Plain(ref mut s) => poll_read_futures(s, cx, buf),
+            MaybeTlsStream::Tls(ref mut s) => poll_read_futures(s, cx, buf),
+        }
+    }
+}
+
+impl<S: FuturesAsyncRead + FuturesAsyncWrite + Unpin> AsyncWrite for MaybeTlsStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<IoResult<usize>> {
+        match self.get_mut() {
+            MaybeTlsStream::Plain(ref mut s) => Pin::new(s).poll_write(cx, buf),
+            MaybeTlsStream::Tls(ref mut s) => Pin::new(s).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        match self.get_mut() {
+            MaybeTlsStream::Plain(ref mut s) => Pin::new(s).poll_flush(cx),
+            MaybeTlsStream::Tls(ref mut s) => Pin::new(s).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        match self.get_mut() {
+            MaybeTlsStream::Plain(ref mut s) => Pin::new(s).poll_close(cx),
+            MaybeTlsStream::Tls(ref mut s) => Pin::new(s).poll_close(cx),
+        }
+    }
+}
+
+/// The type of a caller-supplied TLS connector for this backend.
+pub type Connector = TlsConnector;
+
+async fn wrap_stream<S>(
+    socket: S,
+    domain: String,
+    mode: Mode,
+    tls_connector: Option<Connector>,
+) -> Result<MaybeTlsStream<S>, Error>
+where
+    S: FuturesAsyncRead + FuturesAsyncWrite + Unpin,
+{
+    match mode {
+        Mode::Plain => Ok(MaybeTlsStream::Plain(socket)),
+        Mode::Tls => {
+            let connector = tls_connector.unwrap_or_default();
+            let connected = connector.connect(domain, socket).await;
+            match connected {
+                Err(e) => Err(Error::Io(e)),
+                Ok(s) => Ok(MaybeTlsStream::Tls(s)),
+            }
+        }
+    }
+}
+
+/// Creates a WebSocket handshake from a request and a `futures_io` stream, upgrading the
+/// stream to TLS if required.
+pub async fn client_async_tls<R, S>(
+    request: R,
+    stream: S,
+) -> Result<(WebSocketStream<MaybeTlsStream<S>>, Response), Error>
+where
+    R: IntoClientRequest + Unpin,
+    S: FuturesAsyncRead + FuturesAsyncWrite + Unpin,
+{
+    client_async_tls_with_connector_and_config(request, stream, None, None).await
+}
+
+/// Creates a WebSocket handshake from a request and a `futures_io` stream, upgrading the
+/// stream to TLS if required, using a caller-supplied TLS connector and/or `WebSocketConfig`.
+pub async fn client_async_tls_with_connector_and_config<R, S>(
+    request: R,
+    stream: S,
+    connector: Option<Connector>,
+    config: Option<WebSocketConfig>,
+) -> Result<(WebSocketStream<MaybeTlsStream<S>>, Response), Error>
+where
+    R: IntoClientRequest + Unpin,
+    S: FuturesAsyncRead + FuturesAsyncWrite + Unpin,
+{
+    let request = request.into_client_request()?;
+    let host = domain(&request)?;
+
+    // Make sure we check domain and mode first. URL must be valid.
+    let mode = uri_mode(&request.uri())?;
+
+    let stream = wrap_stream(stream, host, mode, connector).await?;
+    client_async_with_config(request, stream, config).await
+}
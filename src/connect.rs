@@ -4,90 +4,233 @@ use tokio::net::TcpStream;
 
 use tungstenite::client::uri_mode;
 use tungstenite::handshake::client::Response;
+use tungstenite::protocol::WebSocketConfig;
+use tungstenite::stream::Mode;
 use tungstenite::Error;
 
-use super::{client_async, IntoClientRequest, Request, WebSocketStream};
+use super::{client_async, client_async_with_config, IntoClientRequest, Request, WebSocketStream};
+use crate::stream::MaybeTlsStream;
 
-#[cfg(feature = "tls")]
-pub(crate) mod encryption {
-    use native_tls::TlsConnector;
-    use tokio_tls::{TlsConnector as TokioTlsConnector, TlsStream};
+/// A connector that can be used when establishing connections, allowing to control whether
+/// `wss://` URLs are based on plain TCP, TLS or other encryption layer.
+pub enum Connector {
+    /// Plain (non-TLS) connector.
+    Plain,
+    /// `native-tls` TLS connector.
+    #[cfg(feature = "tls")]
+    NativeTls(native_tls::TlsConnector),
+    /// `rustls` TLS connector.
+    #[cfg(feature = "tokio-rustls")]
+    Rustls(std::sync::Arc<rustls::ClientConfig>),
+    /// `openssl` TLS connector.
+    #[cfg(feature = "tokio-openssl")]
+    Openssl(openssl::ssl::SslConnector),
+}
+
+mod encryption {
+    #[cfg(feature = "tls")]
+    pub mod native_tls {
+        use native_tls::TlsConnector;
+        use tokio_tls::TlsConnector as TokioTlsConnector;
+
+        use tokio::io::{AsyncRead, AsyncWrite};
+
+        use tungstenite::Error;
 
-    use tokio::io::{AsyncRead, AsyncWrite};
+        use crate::stream::MaybeTlsStream;
 
-    use tungstenite::stream::Mode;
-    use tungstenite::Error;
+        pub async fn wrap_stream<S>(
+            socket: S,
+            domain: String,
+            tls_connector: Option<TlsConnector>,
+        ) -> Result<MaybeTlsStream<S>, Error>
+        where
+            S: 'static + AsyncRead + AsyncWrite + Send + Unpin,
+        {
+            let connector = match tls_connector {
+                Some(connector) => connector,
+                None => TlsConnector::builder().build().map_err(Error::Tls)?,
+            };
+            let stream = TokioTlsConnector::from(connector);
 
-    use crate::stream::Stream as StreamSwitcher;
+            let connected = stream.connect(&domain, socket).await;
+            match connected {
+                Err(e) => Err(Error::Tls(e)),
+                Ok(s) => Ok(MaybeTlsStream::NativeTls(s)),
+            }
+        }
+    }
 
-    /// A stream that might be protected with TLS.
-    pub type MaybeTlsStream<S> = StreamSwitcher<S, TlsStream<S>>;
+    #[cfg(feature = "tokio-rustls")]
+    pub mod rustls {
+        use std::sync::Arc;
 
-    pub type AutoStream<S> = MaybeTlsStream<S>;
+        use rustls::{ClientConfig, RootCertStore};
+        use tokio_rustls::{webpki::DNSNameRef, TlsConnector};
 
-    pub async fn wrap_stream<S>(
-        socket: S,
-        domain: Option<String>,
-        mode: Mode,
-    ) -> Result<AutoStream<S>, Error>
-    where
-        S: 'static + AsyncRead + AsyncWrite + Send + Unpin,
-    {
-        match mode {
-            Mode::Plain => Ok(StreamSwitcher::Plain(socket)),
-            Mode::Tls => {
-                let mut builder = TlsConnector::builder();
-                builder.danger_accept_invalid_hostnames(true);
-                builder.use_sni(false);
+        use tokio::io::{AsyncRead, AsyncWrite};
 
-                let try_connector = builder.build();
-                let connector = try_connector.map_err(Error::Tls)?;
-                let stream = TokioTlsConnector::from(connector);
+        use tungstenite::Error;
 
-                let domain = domain.unwrap_or_else(|| "".to_string());
+        use crate::stream::MaybeTlsStream;
+
+        // Pick exactly one rustls 0.19 / tokio-rustls 0.22-compatible way to seed the trust
+        // store; mixing in a newer rustls' `OwnedTrustAnchor` API here would silently require
+        // two incompatible rustls major versions in the dependency graph.
+        #[cfg(not(any(
+            feature = "tokio-rustls-native-certs",
+            feature = "tokio-rustls-webpki-roots"
+        )))]
+        compile_error!(
+            "the `tokio-rustls` feature requires enabling `tokio-rustls-native-certs` and/or \
+             `tokio-rustls-webpki-roots` to supply a trust store"
+        );
+
+        fn rootstore() -> Result<RootCertStore, Error> {
+            #[cfg(feature = "tokio-rustls-native-certs")]
+            {
+                rustls_native_certs::load_native_certs().map_err(|(_, e)| Error::Io(e))
+            }
+
+            #[cfg(all(
+                feature = "tokio-rustls-webpki-roots",
+                not(feature = "tokio-rustls-native-certs")
+            ))]
+            {
+                let mut roots = RootCertStore::empty();
+                roots.add_server_trust_anchors(&webpki_roots::TLS_SERVER_ROOTS);
+                Ok(roots)
+            }
+        }
 
-                let connected = stream.connect(&domain, socket).await;
-                match connected {
-                    Err(e) => Err(Error::Tls(e)),
-                    Ok(s) => Ok(StreamSwitcher::Tls(s)),
+        pub async fn wrap_stream<S>(
+            socket: S,
+            domain: String,
+            tls_config: Option<Arc<ClientConfig>>,
+        ) -> Result<MaybeTlsStream<S>, Error>
+        where
+            S: 'static + AsyncRead + AsyncWrite + Send + Unpin,
+        {
+            let config = match tls_config {
+                Some(config) => config,
+                None => {
+                    let mut config = ClientConfig::new();
+                    config.root_store = rootstore()?;
+                    Arc::new(config)
                 }
+            };
+            let connector = TlsConnector::from(config);
+
+            let dnsname = DNSNameRef::try_from_ascii_str(&domain)
+                .map_err(|_| Error::Url("Invalid domain name".into()))?;
+
+            let connected = connector.connect(dnsname, socket).await;
+            match connected {
+                Err(e) => Err(Error::Io(e)),
+                Ok(s) => Ok(MaybeTlsStream::Rustls(s)),
             }
         }
     }
-}
 
-#[cfg(feature = "tls")]
-pub use self::encryption::MaybeTlsStream;
-
-#[cfg(not(feature = "tls"))]
-pub(crate) mod encryption {
-    use tokio::io::{AsyncRead, AsyncWrite};
-
-    use tungstenite::stream::Mode;
-    use tungstenite::Error;
-
-    pub type AutoStream<S> = S;
-
-    pub async fn wrap_stream<S>(
-        socket: S,
-        _domain: Option<String>,
-        mode: Mode,
-    ) -> Result<AutoStream<S>, Error>
-    where
-        S: 'static + AsyncRead + AsyncWrite + Send + Unpin,
-    {
-        match mode {
-            Mode::Plain => Ok(socket),
-            Mode::Tls => Err(Error::Url("TLS support not compiled in.".into())),
+    #[cfg(feature = "tokio-openssl")]
+    pub mod openssl {
+        use std::pin::Pin;
+
+        use openssl::ssl::{SslConnector, SslMethod};
+        use tokio_openssl::SslStream;
+
+        use tokio::io::{AsyncRead, AsyncWrite};
+
+        use tungstenite::Error;
+
+        use crate::stream::MaybeTlsStream;
+
+        fn tls_err(e: impl std::error::Error) -> Error {
+            Error::Io(std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
+        }
+
+        pub async fn wrap_stream<S>(
+            socket: S,
+            domain: String,
+            tls_connector: Option<SslConnector>,
+        ) -> Result<MaybeTlsStream<S>, Error>
+        where
+            S: 'static + AsyncRead + AsyncWrite + Send + Unpin,
+        {
+            let connector = match tls_connector {
+                Some(connector) => connector,
+                None => SslConnector::builder(SslMethod::tls())
+                    .map_err(tls_err)?
+                    .build(),
+            };
+
+            let ssl = connector
+                .configure()
+                .map_err(tls_err)?
+                .into_ssl(&domain)
+                .map_err(tls_err)?;
+
+            let mut stream = SslStream::new(ssl, socket).map_err(tls_err)?;
+            Pin::new(&mut stream).connect().await.map_err(tls_err)?;
+
+            Ok(MaybeTlsStream::Openssl(stream))
         }
     }
 }
 
-use self::encryption::{wrap_stream, AutoStream};
+async fn wrap_stream<S>(
+    socket: S,
+    domain: String,
+    mode: Mode,
+    connector: Option<Connector>,
+) -> Result<MaybeTlsStream<S>, Error>
+where
+    S: 'static + AsyncRead + AsyncWrite + Send + Unpin,
+{
+    match mode {
+        Mode::Plain => Ok(MaybeTlsStream::Plain(socket)),
+        Mode::Tls => match connector {
+            Some(Connector::Plain) => Ok(MaybeTlsStream::Plain(socket)),
+            #[cfg(feature = "tls")]
+            Some(Connector::NativeTls(conn)) => {
+                self::encryption::native_tls::wrap_stream(socket, domain, Some(conn)).await
+            }
+            #[cfg(feature = "tokio-rustls")]
+            Some(Connector::Rustls(conn)) => {
+                self::encryption::rustls::wrap_stream(socket, domain, Some(conn)).await
+            }
+            #[cfg(feature = "tokio-openssl")]
+            Some(Connector::Openssl(conn)) => {
+                self::encryption::openssl::wrap_stream(socket, domain, Some(conn)).await
+            }
+            _ => {
+                #[cfg(feature = "tls")]
+                {
+                    self::encryption::native_tls::wrap_stream(socket, domain, None).await
+                }
+                #[cfg(all(feature = "tokio-rustls", not(feature = "tls")))]
+                {
+                    self::encryption::rustls::wrap_stream(socket, domain, None).await
+                }
+                #[cfg(all(
+                    feature = "tokio-openssl",
+                    not(any(feature = "tls", feature = "tokio-rustls"))
+                ))]
+                {
+                    self::encryption::openssl::wrap_stream(socket, domain, None).await
+                }
+                #[cfg(not(any(feature = "tls", feature = "tokio-rustls", feature = "tokio-openssl")))]
+                {
+                    Err(Error::Url("TLS support not compiled in.".into()))
+                }
+            }
+        },
+    }
+}
 
 /// Get a domain from an URL.
 #[inline]
-fn domain(request: &Request) -> Result<String, Error> {
+pub(crate) fn domain(request: &Request) -> Result<String, Error> {
     match request.uri().host() {
         Some(d) => Ok(d.to_string()),
         None => Err(Error::Url("no host name in the url".into())),
@@ -99,29 +242,48 @@ fn domain(request: &Request) -> Result<String, Error> {
 pub async fn client_async_tls<R, S>(
     request: R,
     stream: S,
-) -> Result<(WebSocketStream<AutoStream<S>>, Response), Error>
+) -> Result<(WebSocketStream<MaybeTlsStream<S>>, Response), Error>
 where
     R: IntoClientRequest + Unpin,
     S: 'static + AsyncRead + AsyncWrite + Send + Unpin,
-    AutoStream<S>: Unpin,
+    MaybeTlsStream<S>: Unpin,
 {
-    let request = request.into_client_request()?;
+    client_async_tls_with_connector_and_config(request, stream, None, None).await
+}
 
-    // Set to None to disable SSL validation
-    // let domain = domain(&request)?;
-    let domain = None;
+/// Creates a WebSocket handshake from a request and a stream, upgrading the stream to TLS
+/// if required, using a caller-supplied TLS connector and/or `WebSocketConfig`.
+///
+/// Passing `None` for the connector builds a default one for whichever backend is compiled
+/// in, which verifies the server's certificate and hostname as normal; to accept invalid
+/// certificates or hostnames, build a connector with that configured explicitly and pass it
+/// in here. Passing `None` for the config uses tungstenite's defaults. Reusing a single
+/// connector across connections also avoids rebuilding the TLS trust store on every call.
+pub async fn client_async_tls_with_connector_and_config<R, S>(
+    request: R,
+    stream: S,
+    connector: Option<Connector>,
+    config: Option<WebSocketConfig>,
+) -> Result<(WebSocketStream<MaybeTlsStream<S>>, Response), Error>
+where
+    R: IntoClientRequest + Unpin,
+    S: 'static + AsyncRead + AsyncWrite + Send + Unpin,
+    MaybeTlsStream<S>: Unpin,
+{
+    let request = request.into_client_request()?;
+    let domain = domain(&request)?;
 
     // Make sure we check domain and mode first. URL must be valid.
     let mode = uri_mode(&request.uri())?;
 
-    let stream = wrap_stream(stream, domain, mode).await?;
-    client_async(request, stream).await
+    let stream = wrap_stream(stream, domain, mode, connector).await?;
+    client_async_with_config(request, stream, config).await
 }
 
 /// Connect to a given URL.
 pub async fn connect_async<R>(
     request: R,
-) -> Result<(WebSocketStream<AutoStream<TcpStream>>, Response), Error>
+) -> Result<(WebSocketStream<MaybeTlsStream<TcpStream>>, Response), Error>
 where
     R: IntoClientRequest + Unpin,
 {
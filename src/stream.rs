@@ -0,0 +1,86 @@
+//! Convenience wrapper for streams to switch between plain TCP and different TLS backends
+//! at runtime.
+//!
+//! There is no dependency on actual TLS implementations. Everything like `native_tls` or
+//! `rustls` is used only behind their respective feature flags.
+
+use std::io::Result as IoResult;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// A stream that might be protected with TLS.
+pub enum MaybeTlsStream<S> {
+    /// Unencrypted socket stream.
+    Plain(S),
+    /// Encrypted socket stream using `native-tls`.
+    #[cfg(feature = "tls")]
+    NativeTls(tokio_tls::TlsStream<S>),
+    /// Encrypted socket stream using `tokio-rustls`.
+    #[cfg(feature = "tokio-rustls")]
+    Rustls(tokio_rustls::client::TlsStream<S>),
+    /// Encrypted socket stream using `tokio-openssl`.
+    #[cfg(feature = "tokio-openssl")]
+    Openssl(tokio_openssl::SslStream<S>),
+}
+
+impl<S: Unpin + AsyncRead + AsyncWrite> AsyncRead for MaybeTlsStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<IoResult<()>> {
+        match self.get_mut() {
+            MaybeTlsStream::Plain(ref mut s) => Pin::new(s).poll_read(cx, buf),
+            #[cfg(feature = "tls")]
+            MaybeTlsStream::NativeTls(ref mut s) => Pin::new(s).poll_read(cx, buf),
+            #[cfg(feature = "tokio-rustls")]
+            MaybeTlsStream::Rustls(ref mut s) => Pin::new(s).poll_read(cx, buf),
+            #[cfg(feature = "tokio-openssl")]
+            MaybeTlsStream::Openssl(ref mut s) => Pin::new(s).poll_read(cx, buf),
+        }
+    }
+}
+
+impl<S: Unpin + AsyncRead + AsyncWrite> AsyncWrite for MaybeTlsStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<IoResult<usize>> {
+        match self.get_mut() {
+            MaybeTlsStream::Plain(ref mut s) => Pin::new(s).poll_write(cx, buf),
+            #[cfg(feature = "tls")]
+            MaybeTlsStream::NativeTls(ref mut s) => Pin::new(s).poll_write(cx, buf),
+            #[cfg(feature = "tokio-rustls")]
+            MaybeTlsStream::Rustls(ref mut s) => Pin::new(s).poll_write(cx, buf),
+            #[cfg(feature = "tokio-openssl")]
+            MaybeTlsStream::Openssl(ref mut s) => Pin::new(s).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        match self.get_mut() {
+            MaybeTlsStream::Plain(ref mut s) => Pin::new(s).poll_flush(cx),
+            #[cfg(feature = "tls")]
+            MaybeTlsStream::NativeTls(ref mut s) => Pin::new(s).poll_flush(cx),
+            #[cfg(feature = "tokio-rustls")]
+            MaybeTlsStream::Rustls(ref mut s) => Pin::new(s).poll_flush(cx),
+            #[cfg(feature = "tokio-openssl")]
+            MaybeTlsStream::Openssl(ref mut s) => Pin::new(s).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        match self.get_mut() {
+            MaybeTlsStream::Plain(ref mut s) => Pin::new(s).poll_shutdown(cx),
+            #[cfg(feature = "tls")]
+            MaybeTlsStream::NativeTls(ref mut s) => Pin::new(s).poll_shutdown(cx),
+            #[cfg(feature = "tokio-rustls")]
+            MaybeTlsStream::Rustls(ref mut s) => Pin::new(s).poll_shutdown(cx),
+            #[cfg(feature = "tokio-openssl")]
+            MaybeTlsStream::Openssl(ref mut s) => Pin::new(s).poll_shutdown(cx),
+        }
+    }
+}